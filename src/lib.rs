@@ -4,6 +4,15 @@
 //! It was quickly replaced by SHA-1 due to a discovered flaw in its design.
 //! SHA-0 is considered obsolete and insecure, and **should not be used for any important or security-critical purposes**.
 //!
+//! This crate also provides [`Sha1`], which shares every piece of SHA-0's
+//! implementation except the one extra bit of rotation in the message
+//! schedule that fixed the flaw. It is included to make that difference
+//! easy to study, not because SHA-1 is fit for security-critical use either.
+//!
+//! For bulk workloads, [`Sha::hash_many`] hashes many independent messages
+//! at once by vectorizing the compression function across messages (rather
+//! than within one) using the `wide` crate's portable SIMD vectors.
+//!
 //! ## Example: Hashing a string
 //!
 //! ```rust
@@ -11,7 +20,7 @@
 //!
 //! let mut hasher = Sha0::new();
 //! hasher.update(b"hello world");
-//! let digest = hasher.finalize();
+//! let digest = hasher.finalize_hex();
 //! println!("SHA-0 digest: {}", digest);
 //! ```
 //!
@@ -31,7 +40,7 @@
 //!     if n == 0 { break; }
 //!     hasher.update(&buffer[..n]);
 //! }
-//! let digest = hasher.finalize();
+//! let digest = hasher.finalize_hex();
 //! println!("SHA-0 digest: {}", digest);
 //! ```
 //!
@@ -43,17 +52,54 @@
 //! let mut hasher = Sha0::new();
 //! hasher.update(b"hello ");
 //! hasher.update(b"world");
-//! let digest = hasher.finalize();
+//! let digest = hasher.finalize_hex();
 //! println!("SHA-0 digest: {}", digest);
 //! ```
-pub struct Sha0 {
-    h: [u32; 5], // Hash state
-    data: Vec<u8>, // Data buffer
-    length: u64, // Total length of input data in bits
+//!
+//! ## Example: Reusing a hasher via reset
+//!
+//! ```rust
+//! use sha0::Sha0;
+//!
+//! let mut hasher = Sha0::new();
+//! hasher.update(b"first message");
+//! let first = hasher.finalize_reset();
+//! hasher.update(b"second message");
+//! let second = hasher.finalize();
+//! assert_ne!(first, second);
+//! ```
+use wide::u32x8;
+
+/// Number of messages [`Sha::hash_many`] hashes side by side in one pass.
+const LANES: usize = 8;
+
+/// Shared SHA-0/SHA-1 implementation.
+///
+/// SHA-0 and SHA-1 differ in exactly one place: when the message schedule
+/// is extended past the first 16 words, SHA-1 rotates each new word left
+/// by one bit and SHA-0 does not. `ROTATE` captures that single bit of
+/// difference so the padding, constants, and round logic only need to be
+/// written once. Use the [`Sha0`] and [`Sha1`] aliases rather than naming
+/// `Sha` directly.
+#[derive(Clone)]
+pub struct Sha<const ROTATE: bool> {
+    h: [u32; 5],        // Hash state
+    buffer: [u8; 64],   // Partial block waiting to be processed
+    buffer_len: usize,  // Number of valid bytes at the front of `buffer`
+    length: u64,        // Total length of input data in bits
 }
 
-impl Sha0 {
-    /// Create a new SHA-0 instance
+/// SHA-0, the original 1993 revision. See the crate-level disclaimer.
+pub type Sha0 = Sha<false>;
+
+/// SHA-1, the 1995 revision of SHA-0 that rotates the extended message
+/// schedule word left by one bit. Provided so the effect of that fix can
+/// be compared directly against [`Sha0`]; still not fit for
+/// security-critical use by modern standards.
+pub type Sha1 = Sha<true>;
+
+impl<const ROTATE: bool> Sha<ROTATE> {
+    /// Create a new hasher instance
     pub fn new() -> Self {
         Self {
             h: [
@@ -63,37 +109,116 @@ impl Sha0 {
                 0x10325476,
                 0xc3d2e1f0,
             ],
-            data: Vec::new(),
+            buffer: [0u8; 64],
+            buffer_len: 0,
             length: 0,
         }
     }
     /// Update the hash with new data
-    pub fn update(&mut self, input: &[u8]) {
+    pub fn update(&mut self, mut input: &[u8]) {
         self.length += (input.len() as u64) * 8;
-        self.data.extend_from_slice(input);
-        while self.data.len() >= 64 {
-            let block = self.data[..64].to_vec();
+        if self.buffer_len > 0 {
+            let needed = 64 - self.buffer_len;
+            let take = needed.min(input.len());
+            self.buffer[self.buffer_len..self.buffer_len + take].copy_from_slice(&input[..take]);
+            self.buffer_len += take;
+            input = &input[take..];
+            if self.buffer_len < 64 {
+                return;
+            }
+            let block = self.buffer;
             self.process_block(&block);
-            self.data.drain(..64);
+            self.buffer_len = 0;
+        }
+        let mut chunks = input.chunks_exact(64);
+        for chunk in &mut chunks {
+            self.process_block(chunk);
         }
+        let remainder = chunks.remainder();
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffer_len = remainder.len();
     }
-    /// Finalize the hash and produce the digest as a hex string
-    pub fn finalize(mut self) -> String {
-        self.pad();
-        while self.data.len() >= 64 {
-            let block = self.data[..64].to_vec();
-            self.process_block(&block);
-            self.data.drain(..64);
+    /// Finalize the hash and produce the digest as raw bytes, without
+    /// consuming or mutating the hasher. Calling this multiple times in a
+    /// row yields the same digest; use [`Sha::finalize_reset`] to also
+    /// start a fresh hasher afterwards.
+    pub fn finalize(&mut self) -> [u8; 20] {
+        let mut state = self.clone();
+        state.pad();
+        let mut out = [0u8; 20];
+        for (chunk, word) in out.chunks_mut(4).zip(state.h.iter()) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        out
+    }
+    /// Finalize the hash and produce the digest as a hex string, without
+    /// consuming or mutating the hasher.
+    pub fn finalize_hex(&mut self) -> String {
+        self.finalize().iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+    /// Finalize the hash, reset the hasher to its initial state, and
+    /// return the digest produced before the reset. This lets a single
+    /// hasher instance hash many messages in sequence.
+    pub fn finalize_reset(&mut self) -> [u8; 20] {
+        let digest = self.finalize();
+        self.reset();
+        digest
+    }
+    /// Reset the hasher to its initial state, as if it had just been
+    /// created with [`Sha::new`].
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+    /// Resume a hasher from a previously produced digest, as if it had
+    /// already absorbed `processed_bits` bits of some unknown message.
+    ///
+    /// This is what makes the classic Merkle-Damgard length-extension
+    /// attack possible: because SHA-0 (like SHA-1) exposes its internal
+    /// state as the digest, knowing `H(m)` and `len(m)` is enough to carry
+    /// on the computation as though `m` itself were known. Pair this with
+    /// [`glue_pad`] to extend a message you can't see; see
+    /// `test_length_extension_attack` for a worked example.
+    pub fn from_state(h: [u32; 5], processed_bits: u64) -> Self {
+        Self {
+            h,
+            buffer: [0u8; 64],
+            buffer_len: 0,
+            length: processed_bits,
         }
-        self.h.iter().map(|word| format!("{:08x}", word)).collect()
     }
-    /// Pad the data buffer as per SHA-0 specification
+    /// Like [`Sha::from_state`], but parses `h` from the 40-character
+    /// lowercase hex digest produced by [`Sha::finalize_hex`].
+    pub fn from_hex_state(hex_digest: &str, processed_bits: u64) -> Self {
+        assert_eq!(hex_digest.len(), 40, "a SHA-0/SHA-1 digest is 40 hex characters");
+        let mut h = [0u32; 5];
+        for (word, chunk) in h.iter_mut().zip(hex_digest.as_bytes().chunks(8)) {
+            let chunk = std::str::from_utf8(chunk).expect("digest is ASCII hex");
+            *word = u32::from_str_radix(chunk, 16).expect("digest is valid hex");
+        }
+        Self::from_state(h, processed_bits)
+    }
+    /// Pad the block buffer as per the SHA-0/SHA-1 specification, processing
+    /// the final one or two blocks as padding fills them.
     fn pad(&mut self) {
-        self.data.push(0x80); // Append 1 bit (0x80 = 10000000)
-        while (self.data.len() % 64) != 56 {
-            self.data.push(0x00);
+        let bit_length = self.length;
+        self.push_pad_byte(0x80); // Append 1 bit (0x80 = 10000000)
+        while self.buffer_len != 56 {
+            self.push_pad_byte(0x00);
+        }
+        for byte in bit_length.to_be_bytes() {
+            self.push_pad_byte(byte); // Append length as 64-bit big endian
+        }
+    }
+    /// Append a single padding byte to the block buffer, processing and
+    /// clearing the buffer whenever it fills up.
+    fn push_pad_byte(&mut self, byte: u8) {
+        self.buffer[self.buffer_len] = byte;
+        self.buffer_len += 1;
+        if self.buffer_len == 64 {
+            let block = self.buffer;
+            self.process_block(&block);
+            self.buffer_len = 0;
         }
-        self.data.extend_from_slice(&self.length.to_be_bytes()); // Append length as 64-bit big endian
     }
     /// Process a 512-bit (64-byte) block
     fn process_block(&mut self, block: &[u8]) {
@@ -103,9 +228,11 @@ impl Sha0 {
         for (i, chunk) in block.chunks(4).enumerate().take(16) {
             w[i] = u32::from_be_bytes(chunk.try_into().unwrap());
         }
-        // No rotation in SHA-0, but we need to extend the message schedule
+        // SHA-1 rotates the extended word left by one bit; SHA-0 does not.
+        // This is the single difference between the two algorithms.
         for t in 16..80 {
-            w[t] = w[t - 3] ^ w[t - 8] ^ w[t - 14] ^ w[t - 16];
+            let word = w[t - 3] ^ w[t - 8] ^ w[t - 14] ^ w[t - 16];
+            w[t] = if ROTATE { word.rotate_left(1) } else { word };
         }
         // Initialize working variables
         let mut a = self.h[0];
@@ -147,6 +274,167 @@ impl Sha0 {
     }
 }
 
+impl<const ROTATE: bool> Sha<ROTATE> {
+    /// Hash many independent messages at once by vectorizing across
+    /// messages rather than within one: each of `u32x8`'s 8 lanes carries
+    /// one message's working variables and hash state, and every round of
+    /// the 80-round recurrence runs lanewise. `inputs` is grouped into
+    /// lane-width batches; output order matches `inputs` order.
+    pub fn hash_many(inputs: &[&[u8]]) -> Vec<[u8; 20]> {
+        inputs.chunks(LANES).flat_map(Self::hash_batch).collect()
+    }
+    /// Hash a single batch of at most [`LANES`] messages side by side.
+    ///
+    /// Messages in a batch are padded independently and so may need
+    /// different numbers of 64-byte blocks; lanes for shorter messages are
+    /// fed zero blocks once they run out, and their hash state is frozen
+    /// (via a lanewise mask) from the round after their own last real
+    /// block onward, so the extra rounds spent on them are wasted but
+    /// harmless.
+    fn hash_batch(batch: &[&[u8]]) -> Vec<[u8; 20]> {
+        let padded: Vec<Vec<u8>> = batch.iter().map(|msg| Self::pad_message(msg)).collect();
+        let lane_blocks: Vec<usize> = padded.iter().map(|p| p.len() / 64).collect();
+        let max_blocks = lane_blocks.iter().copied().max().unwrap_or(0);
+
+        let iv = [0x67452301u32, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+        let mut h: [u32x8; 5] = core::array::from_fn(|i| u32x8::splat(iv[i]));
+        let k = [
+            u32x8::splat(0x5a827999),
+            u32x8::splat(0x6ed9eba1),
+            u32x8::splat(0x8f1bbcdc),
+            u32x8::splat(0xca62c1d6),
+        ];
+
+        for block_index in 0..max_blocks {
+            let mut w = [u32x8::splat(0); 80];
+            for t in 0..16 {
+                let mut lane_words = [0u32; LANES];
+                for (lane, blocks) in lane_blocks.iter().enumerate() {
+                    if block_index < *blocks {
+                        let block = &padded[lane][block_index * 64..block_index * 64 + 64];
+                        lane_words[lane] = u32::from_be_bytes(block[t * 4..t * 4 + 4].try_into().unwrap());
+                    }
+                }
+                w[t] = u32x8::new(lane_words);
+            }
+            for t in 16..80 {
+                let word = w[t - 3] ^ w[t - 8] ^ w[t - 14] ^ w[t - 16];
+                w[t] = if ROTATE { rotate_left(word, 1) } else { word };
+            }
+
+            let (mut a, mut b, mut c, mut d, mut e) = (h[0], h[1], h[2], h[3], h[4]);
+            for t in 0..80 {
+                let (f, kt) = match t {
+                    0..=19 => ((b & c) | ((!b) & d), k[0]),
+                    20..=39 => (b ^ c ^ d, k[1]),
+                    40..=59 => ((b & c) | (b & d) | (c & d), k[2]),
+                    _ => (b ^ c ^ d, k[3]),
+                };
+                let temp = rotate_left(a, 5) + f + e + kt + w[t];
+                e = d;
+                d = c;
+                c = rotate_left(b, 30);
+                b = a;
+                a = temp;
+            }
+
+            let mut active_lanes = [0u32; LANES];
+            for (lane, blocks) in lane_blocks.iter().enumerate() {
+                if block_index < *blocks {
+                    active_lanes[lane] = u32::MAX;
+                }
+            }
+            let active = u32x8::new(active_lanes);
+            h[0] = select(active, h[0] + a, h[0]);
+            h[1] = select(active, h[1] + b, h[1]);
+            h[2] = select(active, h[2] + c, h[2]);
+            h[3] = select(active, h[3] + d, h[3]);
+            h[4] = select(active, h[4] + e, h[4]);
+        }
+
+        let lane_words: [[u32; LANES]; 5] = core::array::from_fn(|i| h[i].to_array());
+        (0..batch.len())
+            .map(|lane| {
+                let mut digest = [0u8; 20];
+                for (word, chunk) in lane_words.iter().zip(digest.chunks_mut(4)) {
+                    chunk.copy_from_slice(&word[lane].to_be_bytes());
+                }
+                digest
+            })
+            .collect()
+    }
+    /// Pad a complete, in-memory message the same way [`Sha::pad`] pads a
+    /// streaming buffer. `hash_many` needs random access to every block of
+    /// every lane up front, so it pads into an owned `Vec` instead of the
+    /// fixed streaming buffer.
+    fn pad_message(msg: &[u8]) -> Vec<u8> {
+        let bit_length = (msg.len() as u64) * 8;
+        let mut padded = msg.to_vec();
+        padded.push(0x80);
+        while padded.len() % 64 != 56 {
+            padded.push(0x00);
+        }
+        padded.extend_from_slice(&bit_length.to_be_bytes());
+        padded
+    }
+}
+
+/// Rotate every lane of `x` left by `n` bits.
+fn rotate_left(x: u32x8, n: u32) -> u32x8 {
+    (x << n) | (x >> (32 - n))
+}
+
+/// Lanewise select: `mask`'s all-ones lanes take `a`, its all-zero lanes
+/// take `b`. Used to freeze a [`Sha::hash_many`] lane's hash state once
+/// that lane's message has no more blocks left.
+fn select(mask: u32x8, a: u32x8, b: u32x8) -> u32x8 {
+    (a & mask) | (b & !mask)
+}
+
+/// Compute the "glue padding" that SHA-0/SHA-1 append after a message of
+/// `original_len` bytes: the `0x80` bit, zero bytes up to 56 (mod 64), and
+/// the original bit length as a big-endian `u64`. Prepend the result to a
+/// chosen suffix and feed both to a hasher resumed with [`Sha::from_state`]
+/// or [`Sha::from_hex_state`] to reproduce a length-extension attack.
+pub fn glue_pad(original_len: u64) -> Vec<u8> {
+    let bit_length = original_len * 8;
+    let mut pad = vec![0x80u8];
+    while (original_len + pad.len() as u64) % 64 != 56 {
+        pad.push(0x00);
+    }
+    pad.extend_from_slice(&bit_length.to_be_bytes());
+    pad
+}
+
+/// A minimal digest trait mirroring the classic rust-crypto `Digest` API
+/// (`input`, `result`, `result_str`, `reset`), so `Sha0` and `Sha1` can be
+/// driven through a common interface alongside other hashers.
+pub trait Digest {
+    /// Feed more data into the hasher.
+    fn input(&mut self, data: &[u8]);
+    /// Produce the digest as raw bytes, without resetting the hasher.
+    fn result(&mut self) -> [u8; 20];
+    /// Produce the digest as a lowercase hex string, without resetting
+    /// the hasher.
+    fn result_str(&mut self) -> String {
+        self.result().iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+    /// Reset the hasher to its initial state.
+    fn reset(&mut self);
+}
+
+impl<const ROTATE: bool> Digest for Sha<ROTATE> {
+    fn input(&mut self, data: &[u8]) {
+        self.update(data);
+    }
+    fn result(&mut self) -> [u8; 20] {
+        self.finalize()
+    }
+    fn reset(&mut self) {
+        Sha::reset(self);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -155,17 +443,141 @@ mod tests {
     fn test_sha0_known_vectors() {
         let mut hasher = Sha0::new();
         hasher.update(b"abc");
-        let digest = hasher.finalize();
+        let digest = hasher.finalize_hex();
         assert_eq!(digest, "0164b8a914cd2a5e74c4f7ff082c4d97f1edf880");
 
         let mut hasher = Sha0::new();
         hasher.update(b"");
-        let digest = hasher.finalize();
+        let digest = hasher.finalize_hex();
         assert_eq!(digest, "f96cea198ad1dd5617ac084a3d92c6107708c0ef");
 
         let mut hasher = Sha0::new();
         hasher.update(b"The quick brown fox jumps over the lazy dog");
-        let digest = hasher.finalize();
+        let digest = hasher.finalize_hex();
         assert_eq!(digest, "b03b401ba92d77666221e843feebf8c561cea5f7");
     }
+
+    #[test]
+    fn test_finalize_does_not_consume_or_mutate() {
+        let mut hasher = Sha0::new();
+        hasher.update(b"abc");
+        let first = hasher.finalize();
+        let second = hasher.finalize();
+        assert_eq!(first, second);
+        assert_eq!(
+            first.iter().map(|byte| format!("{:02x}", byte)).collect::<String>(),
+            "0164b8a914cd2a5e74c4f7ff082c4d97f1edf880"
+        );
+    }
+
+    #[test]
+    fn test_reset_allows_reuse() {
+        let mut hasher = Sha0::new();
+        hasher.update(b"abc");
+        let reused = hasher.finalize_reset();
+        assert_eq!(
+            reused.iter().map(|byte| format!("{:02x}", byte)).collect::<String>(),
+            "0164b8a914cd2a5e74c4f7ff082c4d97f1edf880"
+        );
+
+        hasher.update(b"");
+        assert_eq!(hasher.finalize_hex(), "f96cea198ad1dd5617ac084a3d92c6107708c0ef");
+    }
+
+    #[test]
+    fn test_update_chunking_matches_single_call() {
+        let message = b"The quick brown fox jumps over the lazy dog, and then some more text to span a full 64-byte block and a trailing partial one.";
+
+        let mut one_shot = Sha0::new();
+        one_shot.update(message);
+        let expected = one_shot.finalize_hex();
+
+        for split in [1, 3, 17, 64, 65, 128] {
+            let mut hasher = Sha0::new();
+            for chunk in message.chunks(split) {
+                hasher.update(chunk);
+            }
+            assert_eq!(hasher.finalize_hex(), expected, "split size {split}");
+        }
+    }
+
+    #[test]
+    fn test_sha1_known_vectors() {
+        let mut hasher = Sha1::new();
+        hasher.update(b"abc");
+        assert_eq!(hasher.finalize_hex(), "a9993e364706816aba3e25717850c26c9cd0d89d");
+
+        let mut hasher = Sha1::new();
+        hasher.update(b"");
+        assert_eq!(hasher.finalize_hex(), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+
+        let mut hasher = Sha1::new();
+        hasher.update(b"The quick brown fox jumps over the lazy dog");
+        assert_eq!(hasher.finalize_hex(), "2fd4e1c67a2d28fced849ee1bb76e7391b93eb12");
+    }
+
+    #[test]
+    fn test_sha0_and_sha1_diverge() {
+        let mut sha0 = Sha0::new();
+        sha0.update(b"abc");
+        let mut sha1 = Sha1::new();
+        sha1.update(b"abc");
+        assert_ne!(sha0.finalize(), sha1.finalize());
+    }
+
+    #[test]
+    fn test_hash_many_matches_sequential_finalize() {
+        let messages: Vec<&[u8]> = vec![
+            b"",
+            b"abc",
+            b"The quick brown fox jumps over the lazy dog",
+            b"a longer message that needs more than one 64-byte block once it is padded out with its length suffix",
+            b"short",
+            b"another short one",
+            b"",
+            b"eighth message fills out the first full batch",
+            b"the ninth message starts a second, partial batch",
+        ];
+        assert_eq!(messages.len(), LANES + 1, "exercise a full batch plus a 1-message remainder");
+
+        let expected: Vec<[u8; 20]> = messages
+            .iter()
+            .map(|msg| {
+                let mut hasher = Sha0::new();
+                hasher.update(msg);
+                hasher.finalize()
+            })
+            .collect();
+
+        assert_eq!(Sha0::hash_many(&messages), expected);
+    }
+
+    #[test]
+    fn test_length_extension_attack() {
+        // The defender hashes a secret prefix the attacker can't see.
+        let secret_and_message = b"secretkey!original message";
+        let mut hasher = Sha0::new();
+        hasher.update(secret_and_message);
+        let leaked_digest = hasher.finalize_hex();
+
+        // The attacker only knows the digest, the original length, and a
+        // suffix they want appended; they never see `secret_and_message`.
+        let suffix: &[u8] = b"&admin=true";
+        let glue = glue_pad(secret_and_message.len() as u64);
+        let extended_bits = (secret_and_message.len() + glue.len()) as u64 * 8;
+
+        let mut forged = Sha0::from_hex_state(&leaked_digest, extended_bits);
+        forged.update(suffix);
+        let forged_digest = forged.finalize_hex();
+
+        // The defender could only get this same digest by hashing the
+        // original message plus the glue padding plus the suffix.
+        let mut direct = Sha0::new();
+        direct.update(secret_and_message);
+        direct.update(&glue);
+        direct.update(suffix);
+        let direct_digest = direct.finalize_hex();
+
+        assert_eq!(forged_digest, direct_digest);
+    }
 }